@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use crate::Json;
+
+/// Mirrors the compact vs. pretty split from Rust's old `libserialize::json`:
+/// `Encoder` writes everything on one line, `PrettyEncoder` additionally
+/// tracks an indent level and inserts newlines between container elements.
+enum Encoder {
+    Compact,
+    Pretty { indent: usize },
+}
+
+/// Serialize `json` as pretty-printed JSON, each object/array element on its
+/// own line and indented by `indent` spaces per nesting level.
+///
+/// `Object` is backed by a `HashMap`, so keys are sorted before writing to
+/// keep the output deterministic across runs.
+pub fn to_string_pretty(json: &Json, indent: usize) -> String {
+    let mut out = String::new();
+    write_json(json, &Encoder::Pretty { indent }, 0, &mut out);
+    out
+}
+
+pub(crate) fn to_string_compact(json: &Json) -> String {
+    let mut out = String::new();
+    write_json(json, &Encoder::Compact, 0, &mut out);
+    out
+}
+
+fn write_json(json: &Json, encoder: &Encoder, level: usize, out: &mut String) {
+    match json {
+        Json::Null => out.push_str("null"),
+        Json::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Json::Number(n) => write_number(*n, out),
+        Json::String(s) => write_escaped_string(s, out),
+        Json::Array(items) => write_array(items, encoder, level, out),
+        Json::Object(map) => write_object(map, encoder, level, out),
+    }
+}
+
+fn write_array(items: &[Json], encoder: &Encoder, level: usize, out: &mut String) {
+    if items.is_empty() {
+        out.push_str("[]");
+        return;
+    }
+
+    out.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        newline_indent(encoder, level + 1, out);
+        write_json(item, encoder, level + 1, out);
+    }
+    newline_indent(encoder, level, out);
+    out.push(']');
+}
+
+fn write_object<'a>(map: &HashMap<Cow<'a, str>, Json<'a>>, encoder: &Encoder, level: usize, out: &mut String) {
+    if map.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    let mut keys: Vec<&Cow<str>> = map.keys().collect();
+    keys.sort();
+
+    out.push('{');
+    for (i, key) in keys.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        newline_indent(encoder, level + 1, out);
+        write_escaped_string(key, out);
+        out.push(':');
+        if let Encoder::Pretty { .. } = encoder {
+            out.push(' ');
+        }
+        write_json(&map[key], encoder, level + 1, out);
+    }
+    newline_indent(encoder, level, out);
+    out.push('}');
+}
+
+fn newline_indent(encoder: &Encoder, level: usize, out: &mut String) {
+    if let Encoder::Pretty { indent } = encoder {
+        out.push('\n');
+        out.extend(std::iter::repeat_n(' ', indent * level));
+    }
+}
+
+fn write_number(n: f64, out: &mut String) {
+    // `f64`'s `Display` already picks the shortest round-tripping
+    // representation, so `16.0` formats as `16` with no extra work.
+    let _ = write!(out, "{n}");
+}
+
+fn write_escaped_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    use crate::Json;
+
+    use super::{to_string_compact, to_string_pretty};
+
+    #[test]
+    fn escapes_quotes_backslashes_and_control_chars() {
+        let json = Json::String(Cow::Borrowed("a\"b\\c\n\t\u{1}"));
+        assert_eq!(to_string_compact(&json), "\"a\\\"b\\\\c\\n\\t\\u0001\"");
+    }
+
+    #[test]
+    fn integers_format_without_trailing_dot_zero() {
+        assert_eq!(to_string_compact(&Json::Number(16.0)), "16");
+        assert_eq!(to_string_compact(&Json::Number(1.5)), "1.5");
+    }
+
+    #[test]
+    fn compact_round_trips_through_parse() {
+        let json = r#"{"a":1,"b":[true,false,null,"x"]}"#;
+        let parsed = Json::try_from(json).unwrap();
+        let again = to_string_compact(&parsed);
+        let reparsed = Json::try_from(again.as_str()).unwrap();
+        assert_eq!(to_string_compact(&reparsed), again);
+    }
+
+    #[test]
+    fn pretty_sorts_object_keys_deterministically() {
+        let mut map = HashMap::new();
+        map.insert(Cow::Borrowed("b"), Json::Number(2.0));
+        map.insert(Cow::Borrowed("a"), Json::Number(1.0));
+        let pretty = to_string_pretty(&Json::Object(map), 2);
+        assert!(pretty.find("\"a\"").unwrap() < pretty.find("\"b\"").unwrap());
+    }
+}
@@ -0,0 +1,307 @@
+use std::{borrow::Cow, iter::Peekable};
+
+use crate::{
+    lexer::{Lexer, Meta, Token},
+    parser::Error,
+};
+
+/// A leaf JSON value, as carried by `Event::Value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive<'a> {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Cow<'a, str>),
+}
+
+/// One step of a pull-based JSON parse.
+///
+/// Json = Object | Array | Primary
+///
+/// Object = '{' ( STRING ':' Json [',' STRING ':' Json]* )? '}'
+///
+/// Array = '[' (Json [',' Json]* )? ']'
+///
+/// Primary = "true" | "false" | "null" | STRING | NUMBER
+///
+/// reads as `StartObject (Key Json-events)* EndObject` and
+/// `StartArray Json-events* EndArray`, with every `Json` above one
+/// `Value` or one balanced `Start*`/`End*` pair.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    Key(Cow<'a, str>),
+    EndObject,
+    StartArray,
+    EndArray,
+    Value(Primitive<'a>),
+}
+
+/// Nesting is tracked on this explicit stack instead of recursion, so a
+/// deeply nested document can't blow the call stack.
+enum Frame {
+    Array { started: bool },
+    Object { started: bool, awaiting_value: bool },
+}
+
+/// Copy of the top frame's flags, read out before any token is consumed
+/// so the stack isn't left borrowed while `self` is mutated.
+enum FrameState {
+    Array { started: bool },
+    Object { started: bool, awaiting_value: bool },
+}
+
+/// Pull parser that yields one [`Event`] at a time instead of
+/// materializing a whole `Json` tree, so callers can filter or count
+/// elements of a multi-megabyte array/object without holding it all in
+/// memory. [`crate::parser::Parser::parse`] is built on top of this same
+/// stream.
+pub struct EventParser<'a> {
+    lexer: Peekable<Lexer<'a>>,
+    stack: Vec<Frame>,
+    started: bool,
+    finished: bool,
+}
+
+impl<'a> EventParser<'a> {
+    pub fn new(lexer: Lexer<'a>) -> Self {
+        EventParser {
+            lexer: lexer.peekable(),
+            stack: Vec::new(),
+            started: false,
+            finished: false,
+        }
+    }
+
+    fn advance_unchecked(&mut self) -> Token<'a> {
+        let Some(res) = self.lexer.next() else {
+            panic!("Impossible")
+        };
+        res
+    }
+
+    fn peek_unchecked(&mut self) -> &Token<'a> {
+        let Some(res) = self.lexer.peek() else {
+            panic!("Impossible")
+        };
+        res
+    }
+
+    fn consume(&mut self, expect_lexeme: &Meta<'a>) -> Result<Token<'a>, Error> {
+        let Token { lexeme, line, column, span, .. } = self.peek_unchecked();
+        if lexeme.meta_type() == expect_lexeme.meta_type() {
+            return Ok(self.advance_unchecked());
+        }
+
+        let line = *line;
+        let column = *column;
+        let span = *span;
+        match lexeme {
+            &Meta::Error(msg) => Err(Error::new(msg.to_string(), line, column, span)),
+            _ => Err(Error::new(
+                format!("Expect {}, got {}", expect_lexeme.meta_type(), lexeme.meta_type()),
+                line,
+                column,
+                span,
+            )),
+        }
+    }
+
+    /// Returns `Ok(true)` if a comma was consumed (a value/key must follow),
+    /// `Ok(false)` if `close` is next (the caller's close-handling applies).
+    fn expect_comma_or_close(&mut self, close: &Meta<'a>) -> Result<bool, Error> {
+        let Token { lexeme, line, column, span, .. } = self.peek_unchecked();
+        let is_comma = *lexeme == Meta::Comma;
+        let matches_close = lexeme.meta_type() == close.meta_type();
+        let line = *line;
+        let column = *column;
+        let span = *span;
+
+        if is_comma {
+            self.advance_unchecked();
+            return Ok(true);
+        }
+        if matches_close {
+            return Ok(false);
+        }
+        Err(Error::new(format!("Expect ',' or {}", close.meta_type()), line, column, span))
+    }
+
+    fn start_value(&mut self) -> Result<Event<'a>, Error> {
+        match self.peek_unchecked().lexeme {
+            Meta::LeftBrace => {
+                self.advance_unchecked();
+                self.stack.push(Frame::Object { started: false, awaiting_value: false });
+                Ok(Event::StartObject)
+            }
+            Meta::LeftSquare => {
+                self.advance_unchecked();
+                self.stack.push(Frame::Array { started: false });
+                Ok(Event::StartArray)
+            }
+            _ => {
+                let Token { lexeme, line, column, span, .. } = self.advance_unchecked();
+                match lexeme {
+                    Meta::NullLiteral => Ok(Event::Value(Primitive::Null)),
+                    Meta::BoolLiteral(b) => Ok(Event::Value(Primitive::Bool(b))),
+                    Meta::NumberLiteral(n) => Ok(Event::Value(Primitive::Number(n))),
+                    Meta::StringLiteral(s) => Ok(Event::Value(Primitive::String(s))),
+                    Meta::Error(msg) => Err(Error::new(msg.to_string(), line, column, span)),
+                    _ => Err(Error::new(format!("Expect value, got {}", lexeme.meta_type()), line, column, span)),
+                }
+            }
+        }
+    }
+
+    /// Pulls the next event, fusing the stream (so every later call returns
+    /// `None` instead of re-peeking a lexer that's already reported `done`)
+    /// as soon as any call returns an error, not just on the top-level EOF.
+    fn next_event(&mut self) -> Option<Result<Event<'a>, Error>> {
+        let event = self.next_event_inner();
+        if matches!(event, Some(Err(_))) {
+            self.finished = true;
+        }
+        event
+    }
+
+    fn next_event_inner(&mut self) -> Option<Result<Event<'a>, Error>> {
+        if self.finished {
+            return None;
+        }
+
+        let frame = match self.stack.last() {
+            None => None,
+            Some(Frame::Array { started }) => Some(FrameState::Array { started: *started }),
+            Some(Frame::Object { started, awaiting_value }) => {
+                Some(FrameState::Object { started: *started, awaiting_value: *awaiting_value })
+            }
+        };
+
+        match frame {
+            None => {
+                if self.started {
+                    self.finished = true;
+                    return match self.consume(&Meta::Eof) {
+                        Ok(_) => None,
+                        Err(e) => Some(Err(e)),
+                    };
+                }
+                self.started = true;
+                Some(self.start_value())
+            }
+            Some(FrameState::Array { started }) => {
+                if started {
+                    match self.expect_comma_or_close(&Meta::RightSquare) {
+                        Err(e) => return Some(Err(e)),
+                        // Comma consumed: a value must follow, never a close.
+                        Ok(true) => return Some(self.start_value()),
+                        Ok(false) => {}
+                    }
+                }
+                if self.peek_unchecked().lexeme == Meta::RightSquare {
+                    self.advance_unchecked();
+                    self.stack.pop();
+                    return Some(Ok(Event::EndArray));
+                }
+                if let Some(Frame::Array { started }) = self.stack.last_mut() {
+                    *started = true;
+                }
+                Some(self.start_value())
+            }
+            Some(FrameState::Object { started, awaiting_value }) => {
+                if awaiting_value {
+                    if let Some(Frame::Object { awaiting_value, .. }) = self.stack.last_mut() {
+                        *awaiting_value = false;
+                    }
+                    return Some(self.start_value());
+                }
+                if started {
+                    match self.expect_comma_or_close(&Meta::RightBrace) {
+                        Err(e) => return Some(Err(e)),
+                        // Comma consumed: a key must follow, never a close.
+                        Ok(true) => return Some(self.read_key()),
+                        Ok(false) => {}
+                    }
+                }
+                if self.peek_unchecked().lexeme == Meta::RightBrace {
+                    self.advance_unchecked();
+                    self.stack.pop();
+                    return Some(Ok(Event::EndObject));
+                }
+                Some(self.read_key())
+            }
+        }
+    }
+
+    /// Reads `STRING ':'` and returns the `Key` event, marking the frame
+    /// started and awaiting its value.
+    fn read_key(&mut self) -> Result<Event<'a>, Error> {
+        let key = match self.consume(&Meta::StringLiteral(Cow::Borrowed(""))) {
+            Ok(Token { lexeme: Meta::StringLiteral(key), .. }) => key,
+            Ok(_) => unreachable!("consume guarantees a StringLiteral token"),
+            Err(e) => return Err(e),
+        };
+        self.consume(&Meta::Colon)?;
+        if let Some(Frame::Object { started, awaiting_value }) = self.stack.last_mut() {
+            *started = true;
+            *awaiting_value = true;
+        }
+        Ok(Event::Key(key))
+    }
+}
+
+impl<'a> Iterator for EventParser<'a> {
+    type Item = Result<Event<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::lexer::Lexer;
+
+    use super::{Event, EventParser, Primitive};
+
+    #[test]
+    fn events_for_nested_document() {
+        let json = r#"{"name": "Haruka", "friends": ["Chihaya", "Miki"]}"#;
+        let events: Vec<Event> = EventParser::new(Lexer::new(json)).map(Result::unwrap).collect();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::StartObject,
+                Event::Key(Cow::Borrowed("name")),
+                Event::Value(Primitive::String(Cow::Borrowed("Haruka"))),
+                Event::Key(Cow::Borrowed("friends")),
+                Event::StartArray,
+                Event::Value(Primitive::String(Cow::Borrowed("Chihaya"))),
+                Event::Value(Primitive::String(Cow::Borrowed("Miki"))),
+                Event::EndArray,
+                Event::EndObject,
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_comma_is_rejected() {
+        for json in ["[1,]", r#"{"a":1,}"#] {
+            let events: Vec<_> = EventParser::new(Lexer::new(json)).collect();
+            assert!(events.last().unwrap().is_err(), "expected {json} to error, got {events:?}");
+        }
+    }
+
+    #[test]
+    fn iteration_after_an_error_stays_fused() {
+        let mut parser = EventParser::new(Lexer::new("[[["));
+        for _ in 0..3 {
+            assert_eq!(parser.next().unwrap().unwrap(), Event::StartArray);
+        }
+        assert!(parser.next().unwrap().is_err());
+        assert!(parser.next().is_none());
+        assert!(parser.next().is_none());
+    }
+}
@@ -1,3 +1,13 @@
+use std::borrow::Cow;
+
+/// A half-open byte range `[start, end)` into the original source string,
+/// used to render diagnostics without re-scanning the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Meta<'a> {
     LeftBrace,
@@ -6,7 +16,7 @@ pub enum Meta<'a> {
     RightSquare,
     Comma,
     Colon,
-    StringLiteral(&'a str),
+    StringLiteral(Cow<'a, str>),
     BoolLiteral(bool),
     NumberLiteral(f64),
     NullLiteral,
@@ -38,11 +48,13 @@ pub struct Token<'a> {
     pub literal: &'a str,
     pub line: usize,
     pub column: usize,
+    pub span: Span,
 }
 
 #[derive(Debug)]
 pub struct Lexer<'a> {
     start: &'a str,
+    len: usize,
     line: usize,
     column: usize,
     done: bool,
@@ -52,6 +64,7 @@ impl<'a> Lexer<'a> {
     pub fn new(source: &'a str) -> Self {
         Lexer {
             start: source,
+            len: source.len(),
             line: 1,
             column: 1,
             done: false,
@@ -75,7 +88,7 @@ impl<'a> Lexer<'a> {
             '}' => self.mk_token(Meta::RightBrace, "}"),
             ',' => self.mk_token(Meta::Comma, ","),
             ':' => self.mk_token(Meta::Colon, ":"),
-            _ if c.is_digit(10) || c == '-' => self.number(),
+            _ if c.is_ascii_digit() || c == '-' => self.number(),
             '"' => self.string(),
             _ if c.is_alphabetic() => self.identifier(),
             _ => self.mk_error("Unexpected character", &self.start[0..c.len_utf8()]),
@@ -99,47 +112,140 @@ impl<'a> Lexer<'a> {
     }
 
     fn string(&mut self) -> Token<'a> {
-        let mut chars = self.start.chars().peekable();
+        // `idx` always points one byte past the last character consumed so
+        // far; `decoded` stays `None` until the first escape forces us to
+        // build an owned buffer, so escape-free strings keep borrowing
+        // directly from `self.start`.
         let mut idx = 1;
-        chars.next();
+        let mut decoded: Option<String> = None;
 
-        let mut skip_next = false;
-        while let Some(&c) = chars.peek() {
-            if c == '\n' {
-                return self.mk_error("Unexpected newline", &self.start[..idx]);
-            }
+        loop {
+            let Some(c) = self.start[idx..].chars().next() else {
+                return self.mk_error("Undetermined string literal", &self.start[..idx]);
+            };
 
-            idx += c.len_utf8();
-            chars.next();
-            if skip_next {
-                skip_next = false;
-                continue;
-            }
             match c {
+                '\n' => return self.mk_error("Unexpected newline", &self.start[..idx]),
+                '"' => {
+                    idx += 1;
+                    let literal = &self.start[..idx];
+                    let value = match decoded {
+                        Some(s) => Cow::Owned(s),
+                        None => Cow::Borrowed(&self.start[1..idx - 1]),
+                    };
+                    return self.mk_token(Meta::StringLiteral(value), literal);
+                }
                 '\\' => {
-                    skip_next = true;
+                    let buf = decoded.get_or_insert_with(|| self.start[1..idx].to_string());
+                    idx += 1;
+                    match self.decode_escape(idx, buf) {
+                        Ok(new_idx) => idx = new_idx,
+                        Err(err) => return err,
+                    }
                 }
-                '"' => {
-                    if skip_next {
-                        skip_next = false;
-                        continue;
+                c => {
+                    if let Some(buf) = decoded.as_mut() {
+                        buf.push(c);
                     }
-                    return self.mk_token(
-                        Meta::StringLiteral(&self.start[1..idx - 1]),
-                        &self.start[..idx],
-                    );
+                    idx += c.len_utf8();
                 }
-                _ => (),
-            };
+            }
+        }
+    }
+
+    /// Decodes a single escape sequence starting right after the `\\` at
+    /// byte offset `idx`, appending the resulting character(s) to `buf` and
+    /// returning the byte offset just past the escape. `\uXXXX` surrogate
+    /// pairs are combined into a single code point here as well.
+    fn decode_escape(&mut self, idx: usize, buf: &mut String) -> Result<usize, Token<'a>> {
+        let Some(c) = self.start[idx..].chars().next() else {
+            return Err(self.mk_error("Unterminated escape sequence", &self.start[..idx]));
+        };
+
+        Ok(match c {
+            '"' | '\\' | '/' => {
+                buf.push(c);
+                idx + 1
+            }
+            'b' => {
+                buf.push('\u{8}');
+                idx + 1
+            }
+            'f' => {
+                buf.push('\u{c}');
+                idx + 1
+            }
+            'n' => {
+                buf.push('\n');
+                idx + 1
+            }
+            'r' => {
+                buf.push('\r');
+                idx + 1
+            }
+            't' => {
+                buf.push('\t');
+                idx + 1
+            }
+            'u' => {
+                let (code_point, new_idx) = self.decode_unicode_escape(idx + 1)?;
+                let Some(ch) = char::from_u32(code_point) else {
+                    return Err(self.mk_error("Invalid \\u escape", &self.start[..new_idx]));
+                };
+                buf.push(ch);
+                new_idx
+            }
+            _ => return Err(self.mk_error("Unknown escape sequence", &self.start[..idx + c.len_utf8()])),
+        })
+    }
+
+    /// Parses the 4 hex digits after `\u` at byte offset `idx`, combining a
+    /// high surrogate with a following `\uXXXX` low surrogate into one code
+    /// point. Returns the combined code point and the byte offset just past
+    /// what was consumed.
+    fn decode_unicode_escape(&mut self, idx: usize) -> Result<(u32, usize), Token<'a>> {
+        let hi = self.parse_hex4(idx)?;
+        let idx = idx + 4;
+
+        if !(0xD800..=0xDBFF).contains(&hi) {
+            if (0xDC00..=0xDFFF).contains(&hi) {
+                return Err(self.mk_error("Lone low surrogate in \\u escape", &self.start[..idx]));
+            }
+            return Ok((hi, idx));
+        }
+
+        if !self.start[idx..].starts_with('\\') || !self.start[idx + 1..].starts_with('u') {
+            return Err(self.mk_error(
+                "High surrogate not followed by low surrogate",
+                &self.start[..idx],
+            ));
+        }
+
+        let lo = self.parse_hex4(idx + 2)?;
+        let idx = idx + 6;
+        if !(0xDC00..=0xDFFF).contains(&lo) {
+            return Err(self.mk_error(
+                "High surrogate not followed by low surrogate",
+                &self.start[..idx],
+            ));
+        }
+
+        let code_point = 0x10000 + (hi - 0xD800) * 0x400 + (lo - 0xDC00);
+        Ok((code_point, idx))
+    }
+
+    fn parse_hex4(&mut self, idx: usize) -> Result<u32, Token<'a>> {
+        match self.start.get(idx..idx + 4).and_then(|s| u32::from_str_radix(s, 16).ok()) {
+            Some(value) => Ok(value),
+            None => Err(self.mk_error("Expect 4 hex digits after \\u", &self.start[..idx])),
         }
-        return self.mk_error("Undetermined string literal", &self.start[..idx]);
     }
 
     fn number(&mut self) -> Token<'a> {
         let mut chars = self.start.chars().peekable();
         let mut idx = 0;
 
-        // 匹配-，检查其后是否是数字
+        // Optional leading '-', which must be followed by a digit.
         if chars.peek() == Some(&'-') {
             idx += 1;
             chars.next();
@@ -147,52 +253,87 @@ impl<'a> Lexer<'a> {
                 None => {
                     return self.mk_error("Unexpected EOF after '-'", &self.start[..idx]);
                 }
-                Some(c) if !c.is_digit(10) => {
+                Some(c) if !c.is_ascii_digit() => {
                     return self.mk_error("Expect numeric literal after '-'", &self.start[..idx]);
                 }
                 _ => (),
             };
         }
 
-        // 匹配.前的数字
-        while let Some(&c) = chars.peek() {
-            if !c.is_digit(10) {
-                break;
-            }
+        // Integer part: '0' on its own, or a nonzero digit followed by any
+        // digits; leading zeros like "01" are rejected.
+        if chars.peek() == Some(&'0') {
             idx += 1;
             chars.next();
+            if matches!(chars.peek(), Some(c) if c.is_ascii_digit()) {
+                return self.mk_error("Leading zero is not allowed", &self.start[..idx + 1]);
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                idx += 1;
+                chars.next();
+            }
         }
 
-        // 如果没有.，直接返回
-        if chars.peek() != Some(&'.') {
-            let literal = &self.start[..idx];
-            // should not fail?
-            return self.mk_token(Meta::NumberLiteral(literal.parse().unwrap()), literal);
-        }
+        // Fraction part: '.' must be followed by at least one digit.
+        if chars.peek() == Some(&'.') {
+            idx += 1;
+            chars.next();
 
-        idx += 1;
-        chars.next();
+            match chars.peek() {
+                None => {
+                    return self.mk_error("Unexpected EOF after '.'", &self.start[..idx]);
+                }
+                Some(c) if !c.is_ascii_digit() => {
+                    return self.mk_error("Expect numeric literal after '.'", &self.start[..idx]);
+                }
+                _ => (),
+            };
 
-        match chars.peek() {
-            None => {
-                return self.mk_error("Unexpected EOF after '.'", &self.start[..idx]);
-            }
-            Some(c) if !c.is_digit(10) => {
-                return self.mk_error("Expect numeric literal after '.'", &self.start[..idx]);
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                idx += 1;
+                chars.next();
             }
-            _ => (),
-        };
+        }
 
-        while let Some(&c) = chars.peek() {
-            if !c.is_digit(10) {
-                break;
-            }
+        // Exponent part: [eE][+-]?digit+
+        if chars.peek() == Some(&'e') || chars.peek() == Some(&'E') {
             idx += 1;
             chars.next();
+
+            if chars.peek() == Some(&'+') || chars.peek() == Some(&'-') {
+                idx += 1;
+                chars.next();
+            }
+
+            match chars.peek() {
+                None => {
+                    return self.mk_error("Expect digit after exponent", &self.start[..idx]);
+                }
+                Some(c) if !c.is_ascii_digit() => {
+                    return self.mk_error("Expect digit after exponent", &self.start[..idx]);
+                }
+                _ => (),
+            };
+
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                idx += 1;
+                chars.next();
+            }
         }
+
         let literal = &self.start[..idx];
         // should not fail?
-        return self.mk_token(Meta::NumberLiteral(literal.parse().unwrap()), literal);
+        self.mk_token(Meta::NumberLiteral(literal.parse().unwrap()), literal)
     }
 
     fn manipulate_states(&mut self, literal: &'a str) {
@@ -219,9 +360,12 @@ impl<'a> Lexer<'a> {
 
     fn mk_token(&mut self, meta: Meta<'a>, literal: &'a str) -> Token<'a> {
         let &mut Lexer { line, column, .. } = self;
+        let start = self.len - self.start.len();
+        let span = Span { start, end: start + literal.len() };
         let res: Token<'a> = Token {
             line,
             column,
+            span,
             lexeme: meta,
             literal,
         };
@@ -262,6 +406,7 @@ mod tests {
     use super::Lexer;
     use crate::lexer::{self, Meta, Token};
     use indoc::indoc;
+    use std::borrow::Cow;
 
     fn test() {
         let mut token;
@@ -281,6 +426,7 @@ mod tests {
             line,
             column,
             literal,
+            ..
         }) = token
         else {
             panic!("Expect Some")
@@ -321,21 +467,74 @@ mod tests {
             line,
             column,
             literal,
+            ..
         } = lexer.next().unwrap();
         let Meta::Error(_) = lexeme else { panic!() };
 
         assert_eq!((line, column, literal), (1, 1, "-"));
     }
+
+    #[test]
+    fn exponents() {
+        for (str, expect) in [
+            ("1e10", 1e10),
+            ("6.022e23", 6.022e23),
+            ("-1.5E-9", -1.5E-9),
+            ("2e+3", 2e3),
+        ] {
+            assert_token(Lexer::new(str).next(), (Meta::NumberLiteral(expect), 1, 1, str));
+        }
+    }
+
+    #[test]
+    fn leading_zero_is_rejected() {
+        for str in ["01", "-01", "00"] {
+            let Token { lexeme, .. } = Lexer::new(str).next().unwrap();
+            let Meta::Error(_) = lexeme else { panic!("expected {str} to error") };
+        }
+    }
+
+    #[test]
+    fn digit_required_after_exponent() {
+        for str in ["1e", "1e+", "1E-"] {
+            let Token { lexeme, .. } = Lexer::new(str).next().unwrap();
+            let Meta::Error(_) = lexeme else { panic!("expected {str} to error") };
+        }
+    }
+
     #[test]
     fn strings() {
         assert_token(
             Lexer::new(r#"  "泥嚎"  "#).next(),
-            (Meta::StringLiteral("泥嚎"), 1, 3, r#""泥嚎""#),
+            (Meta::StringLiteral(Cow::Borrowed("泥嚎")), 1, 3, r#""泥嚎""#),
         );
         let Token { lexeme, .. } = Lexer::new(r#"  "泥嚎  "#).next().unwrap();
         let Meta::Error(_) = lexeme else { panic!() };
     }
 
+    #[test]
+    fn string_escapes() {
+        assert_token(
+            Lexer::new(r#""a\nb\t\"c\"""#).next(),
+            (
+                Meta::StringLiteral(Cow::Owned("a\nb\t\"c\"".to_string())),
+                1,
+                1,
+                r#""a\nb\t\"c\"""#,
+            ),
+        );
+
+        // a \u BMP escape and a \u surrogate pair combine into the expected chars
+        let Token { lexeme, .. } = Lexer::new(r#""\u6CE5\uD83D\uDE00""#).next().unwrap();
+        assert_eq!(lexeme, Meta::StringLiteral(Cow::Owned("泥😀".to_string())));
+
+        let Token { lexeme, .. } = Lexer::new(r#""\uD83D""#).next().unwrap();
+        let Meta::Error(_) = lexeme else { panic!("expected lone high surrogate to error") };
+
+        let Token { lexeme, .. } = Lexer::new(r#""\q""#).next().unwrap();
+        let Meta::Error(_) = lexeme else { panic!("expected unknown escape to error") };
+    }
+
     #[test]
     fn it_works() {
         let mut lexer = Lexer::new("[12450]\n[-2.00] null true false");
@@ -369,20 +568,20 @@ mod tests {
             &mut lexer,
             vec![
                 (Meta::LeftBrace, 1, 1, "{"),
-                (Meta::StringLiteral("name"), 2, 5, "\"name\""),
+                (Meta::StringLiteral(Cow::Borrowed("name")), 2, 5, "\"name\""),
                 (Meta::Colon, 2, 11, ":"),
-                (Meta::StringLiteral("Haruka"), 2, 13, "\"Haruka\""),
+                (Meta::StringLiteral(Cow::Borrowed("Haruka")), 2, 13, "\"Haruka\""),
                 (Meta::Comma, 2, 21, ","),
-                (Meta::StringLiteral("age"), 3, 5, "\"age\""),
+                (Meta::StringLiteral(Cow::Borrowed("age")), 3, 5, "\"age\""),
                 (Meta::Colon, 3, 10, ":"),
                 (Meta::NumberLiteral(16.0), 3, 12, "16"),
                 (Meta::Comma, 3, 14, ","),
-                (Meta::StringLiteral("friends"), 4, 5, "\"friends\""),
+                (Meta::StringLiteral(Cow::Borrowed("friends")), 4, 5, "\"friends\""),
                 (Meta::Colon, 4, 14, ":"),
                 (Meta::LeftSquare, 4, 16, "["),
-                (Meta::StringLiteral("Chihaya"), 4, 17, "\"Chihaya\""),
+                (Meta::StringLiteral(Cow::Borrowed("Chihaya")), 4, 17, "\"Chihaya\""),
                 (Meta::Comma, 4, 26, ","),
-                (Meta::StringLiteral("Miki"), 4, 28, "\"Miki\""),
+                (Meta::StringLiteral(Cow::Borrowed("Miki")), 4, 28, "\"Miki\""),
                 (Meta::RightSquare, 4, 34, "]"),
                 (Meta::RightBrace, 5, 1, "}"),
                 (Meta::Eof, 6, 1, ""),
@@ -1,40 +1,86 @@
 
+mod encoder;
+mod events;
 mod lexer;
 mod parser;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+pub use encoder::to_string_pretty;
+pub use events::{Event, EventParser, Primitive};
+use lexer::Lexer;
+use parser::Parser;
+
 #[derive(Debug)]
-pub enum Json {
+pub enum Json<'a> {
     Null,
     Bool(bool),
     Number(f64),
-    String(String),
-    Array(Vec<Json>),
-    Object(HashMap<String, Json>)
+    String(Cow<'a, str>),
+    Array(Vec<Json<'a>>),
+    Object(HashMap<Cow<'a, str>, Json<'a>>),
 }
 
+impl<'a> Json<'a> {
+    /// Detaches this tree from the source buffer it was parsed from,
+    /// cloning any borrowed strings so the result can outlive it.
+    pub fn into_owned(self) -> Json<'static> {
+        match self {
+            Json::Null => Json::Null,
+            Json::Bool(b) => Json::Bool(b),
+            Json::Number(n) => Json::Number(n),
+            Json::String(s) => Json::String(Cow::Owned(s.into_owned())),
+            Json::Array(items) => Json::Array(items.into_iter().map(Json::into_owned).collect()),
+            Json::Object(map) => Json::Object(
+                map.into_iter()
+                    .map(|(k, v)| (Cow::Owned(k.into_owned()), v.into_owned()))
+                    .collect(),
+            ),
+        }
+    }
+}
 
-impl TryFrom<&str> for Json {
+impl<'a> TryFrom<&'a str> for Json<'a> {
     type Error = parser::Error;
-    fn try_from(_value: &str) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Parser::new(Lexer::new(value)).parse()
     }
 }
 
-impl From<Json> for String {
-    fn from(_value: Json) -> Self {
-        todo!()
+impl<'a> From<Json<'a>> for String {
+    fn from(value: Json<'a>) -> Self {
+        encoder::to_string_compact(&value)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    
+    use std::borrow::Cow;
+
+    use super::Json;
 
-    // use super::*;
-    
     #[test]
     fn it_works() {
     }
+
+    #[test]
+    fn strings_without_escapes_borrow_from_the_source() {
+        let source = String::from(r#"{"name": "Haruka"}"#);
+        let Json::Object(map) = Json::try_from(source.as_str()).unwrap() else { panic!() };
+        let Some(Json::String(name)) = map.get("name") else { panic!() };
+        assert!(matches!(name, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn into_owned_detaches_from_the_source_buffer() {
+        let owned: Json<'static> = {
+            let source = String::from(r#"{"name": "Haruka"}"#);
+            Json::try_from(source.as_str()).unwrap().into_owned()
+        };
+        let Json::Object(map) = owned else { panic!() };
+        let Some(Json::String(name)) = map.get("name") else { panic!() };
+        assert_eq!(name.as_ref(), "Haruka");
+        assert!(matches!(name, Cow::Owned(_)));
+    }
 }
@@ -1,8 +1,8 @@
-use std::{collections::HashMap, iter::Peekable};
-
+use std::{collections::HashMap, fmt};
 
 use crate::{
-    lexer::{Lexer, Meta, Token},
+    events::{Event, EventParser, Primitive},
+    lexer::{Lexer, Span},
     Json,
 };
 
@@ -21,130 +21,118 @@ pub struct Error {
     msg: String,
     line: usize,
     column: usize,
-    // token: Token,
+    span: Span,
+}
+
+impl Error {
+    pub(crate) fn new(msg: String, line: usize, column: usize, span: Span) -> Error {
+        Error { msg, line, column, span }
+    }
+
+    /// Renders a `codespan-reporting`-style diagnostic: the offending
+    /// source line, a caret underline spanning the bad token, and a
+    /// `line:column: msg` header.
+    pub fn render(&self, source: &str) -> String {
+        let line_start = source[..self.span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[self.span.start..]
+            .find('\n')
+            .map_or(source.len(), |i| self.span.start + i);
+        let line_text = &source[line_start..line_end];
+
+        // Columns are char counts, not byte counts, so a caret lines up
+        // correctly under multi-byte characters earlier on the line.
+        let underline_start = line_text[..self.span.start - line_start].chars().count();
+        let span_end = (self.span.end - line_start).min(line_text.len());
+        let underline_len = line_text[self.span.start - line_start..span_end].chars().count().max(1);
+
+        format!(
+            "{}:{}: {}\n{}\n{}{}",
+            self.line,
+            self.column,
+            self.msg,
+            line_text,
+            " ".repeat(underline_start),
+            "^".repeat(underline_len),
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.msg)
+    }
 }
 
 
 
-type ParseResult = Result<Json, Error>;
+type ParseResult<'a> = Result<Json<'a>, Error>;
 
+/// Builds a `Json` tree from the [`crate::events::EventParser`] stream, so
+/// the grammar rules live in one place whether a caller wants the whole
+/// tree or wants to drive the events directly.
 pub struct Parser<'a> {
-    lexer: Peekable<Lexer<'a>>,
+    events: EventParser<'a>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: Lexer<'a>) -> Parser<'a> {
-        Parser { lexer: lexer.peekable() }
-    }
-    pub fn parse(&mut self) -> ParseResult {
-        let res = self.json()?;
-        self.consume(&Meta::Eof)?;
-        Ok(res)
+        Parser { events: EventParser::new(lexer) }
     }
 
-    fn advance_unchecked(&mut self) -> Token {
-        let Some(res) = self.lexer.next() else {
-            panic!("Impossible")
-        };
-        res
+    pub fn parse(&mut self) -> ParseResult<'a> {
+        let value = self.build_value()?;
+        match self.events.next() {
+            None => Ok(value),
+            Some(Ok(event)) => unreachable!("Expect end of input, got {event:?}"),
+            Some(Err(e)) => Err(e),
+        }
     }
 
-    fn peek_unchecked(&mut self) -> &Token {
-        let Some(res) = self.lexer.peek() else {
-            panic!("Impossible")
-        };
-        res
+    fn next_event(&mut self) -> Result<Event<'a>, Error> {
+        self.events.next().expect("EventParser never stops mid-value")
     }
 
-    fn consume(&mut self, expect_lexeme: &Meta<'a>) -> Result<Token, Error> {
-        let Token { lexeme, line, column, .. } = self.peek_unchecked();
-        if lexeme.meta_type() == expect_lexeme.meta_type() {
-            return Ok(self.lexer.next().unwrap())
-        }
-        
-        let line = *line;
-        let column = *column;
-        match lexeme {
-            &Meta::Error(msg) => {
-                Err(Error { msg: String::from(msg), line, column })
-            }
-            _ => {
-                Err(Error { msg: format!("Expect {}, got {}", expect_lexeme.meta_type(), lexeme.meta_type()), line, column })
-            }
-        }
+    fn build_value(&mut self) -> ParseResult<'a> {
+        let event = self.next_event()?;
+        self.build_from_event(event)
     }
 
-    fn json(&mut self) -> ParseResult {
-        let Token { lexeme, .. } = self.peek_unchecked();
-        match lexeme {
-            Meta::LeftBrace => self.object(),
-            Meta::LeftSquare => self.array(),
-            _ => self.primary()
+    fn build_from_event(&mut self, event: Event<'a>) -> ParseResult<'a> {
+        match event {
+            Event::Value(Primitive::Null) => Ok(Json::Null),
+            Event::Value(Primitive::Bool(b)) => Ok(Json::Bool(b)),
+            Event::Value(Primitive::Number(n)) => Ok(Json::Number(n)),
+            Event::Value(Primitive::String(s)) => Ok(Json::String(s)),
+            Event::StartArray => self.build_array(),
+            Event::StartObject => self.build_object(),
+            event => unreachable!("Expect value event, got {event:?}"),
         }
     }
 
-    fn read_kv(&mut self) -> Result<(String, Json), Error> {
-        let Token { lexeme: Meta::StringLiteral(key), .. } = self.consume(&Meta::StringLiteral(""))? else {
-            panic!("Impossible")
-        };
-        let key = String::from(key);
-        self.consume(&Meta::Colon)?;
-        let value = self.json()?;
-        Ok((key, value))
-    }
-
-    fn object(&mut self) -> ParseResult {
-        self.consume(&Meta::LeftBrace)?;
-        if self.peek_unchecked().lexeme == Meta::RightBrace {
-            self.consume(&Meta::RightBrace)?;
-            return Ok(Json::Object(HashMap::with_capacity(0)));
-        } 
-
-        let mut result: HashMap<String, Json> = HashMap::new();
-        let (k, v) = self.read_kv()?;
-        result.insert(k, v);
-
-        while self.peek_unchecked().lexeme == Meta::Comma {
-            self.advance_unchecked();
-            let (k, v) = self.read_kv()?;
-            result.insert(k, v);
+    fn build_array(&mut self) -> ParseResult<'a> {
+        let mut items = Vec::new();
+        loop {
+            let event = self.next_event()?;
+            if matches!(event, Event::EndArray) {
+                break;
+            }
+            items.push(self.build_from_event(event)?);
         }
-        self.consume(&Meta::RightBrace)?;
-        Ok(Json::Object(result))
+        Ok(Json::Array(items))
     }
-    fn array(&mut self) -> ParseResult {
-        self.consume(&Meta::LeftSquare)?;
-        if self.peek_unchecked().lexeme == Meta::RightSquare {
-            self.consume(&Meta::RightSquare)?;
-            return Ok(Json::Array(Vec::with_capacity(0)));
-        } 
-        
-        let mut result: Vec<Json> = Vec::new();
-        let v = self.json()?;
-        result.push(v);
-
-        while self.peek_unchecked().lexeme == Meta::Comma {
-            self.advance_unchecked();
-            let v = self.json()?;
-            result.push(v);
+
+    fn build_object(&mut self) -> ParseResult<'a> {
+        let mut map = HashMap::new();
+        loop {
+            let key = match self.next_event()? {
+                Event::EndObject => break,
+                Event::Key(key) => key,
+                event => unreachable!("Expect key or end-of-object event, got {event:?}"),
+            };
+            let value = self.build_value()?;
+            map.insert(key, value);
         }
-        self.consume(&Meta::RightSquare)?;
-        Ok(Json::Array(result))
-    }
-    fn primary(&mut self) -> ParseResult {
-        let Token { lexeme, line, column, .. } = self.advance_unchecked();
-        Ok(match lexeme {
-            Meta::NullLiteral => Json::Null,
-            Meta::BoolLiteral(bool) => Json::Bool(bool),
-            Meta::StringLiteral(str) => Json::String(String::from(str)),
-            Meta::NumberLiteral(num) => Json::Number(num),
-            Meta::Error(msg) => {
-                return Err(Error { msg: String::from(msg), line, column })
-            }
-            _ => {
-                return Err(Error { msg: format!("Expect Primary, got {}", lexeme.meta_type()), line, column })
-            }
-        })
+        Ok(Json::Object(map))
     }
 }
 
@@ -176,4 +164,30 @@ mod tests {
         let mut parser = Parser::new(Lexer::new(json));
         dbg!(parser.parse());
     }
+
+    #[test]
+    fn render_points_at_the_bad_token() {
+        let json = indoc!(
+            r##"
+        {
+            "name" 16
+        }
+        "##
+        );
+        let err = Parser::new(Lexer::new(json)).parse().unwrap_err();
+        let rendered = err.render(json);
+        assert!(rendered.starts_with("2:12:"));
+        assert!(rendered.contains("\"name\" 16"));
+        assert!(rendered.contains("^"));
+    }
+
+    #[test]
+    fn render_aligns_caret_past_non_ascii_content() {
+        let json = r#"{"泥嚎": 1 2}"#;
+        let err = Parser::new(Lexer::new(json)).parse().unwrap_err();
+        let rendered = err.render(json);
+        let caret_line = rendered.lines().last().unwrap();
+        let bad_token_column = json.chars().take_while(|&c| c != '2').count();
+        assert_eq!(caret_line.chars().take_while(|&c| c == ' ').count(), bad_token_column);
+    }
 }
\ No newline at end of file